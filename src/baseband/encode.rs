@@ -49,6 +49,74 @@ impl<T: Iterator<Item = bits::Dibit>> Iterator for C4fmImpulses<T> {
     }
 }
 
+/// Taps for the P25 C4FM shaping filter: a raised-cosine Nyquist filter
+/// with a 9-symbol span and 0.2 excess bandwidth, normalized to unity DC gain. At
+/// `consts::SYMBOL_PERIOD` == 10 samples/symbol, a 9-symbol span is 90 sample
+/// intervals, or 91 taps centered on the peak -- `h[n] = sinc(n/10) * cos(0.2*pi*n/10)
+/// / (1 - (0.4*n/10)^2)` for `n` in `-45..=45`, with the removable singularity at
+/// `|n/10| == 1/(2*0.2)` taken as its limit. `C4fmImpulses` emits one nonzero sample
+/// per symbol period rather than holding it for the whole period, so its spectrum
+/// needs no separate `sin(x)/x` compensation -- this Nyquist filter alone shapes the
+/// transmit waveform.
+const SHAPING_TAPS: &'static [f32] = &[
+    0.003006, 0.003053, 0.002770, 0.002144, 0.001200, 0.000000, -0.001357, -0.002742,
+    -0.004006, -0.004998, -0.005574, -0.005621, -0.005069, -0.003903, -0.002175, 0.000000,
+    0.002443, 0.004927, 0.007191, 0.008968, 0.010010, 0.010112, 0.009145, 0.007071,
+    0.003961, 0.000000, -0.004516, -0.009198, -0.013588, -0.017188, -0.019508, -0.020100,
+    -0.018604, -0.014783, -0.008552, 0.000000, 0.010612, 0.022854, 0.036154, 0.049827,
+    0.063130, 0.075303, 0.085633, 0.093499, 0.098421, 0.100096, 0.098421, 0.093499,
+    0.085633, 0.075303, 0.063130, 0.049827, 0.036154, 0.022854, 0.010612, 0.000000,
+    -0.008552, -0.014783, -0.018604, -0.020100, -0.019508, -0.017188, -0.013588, -0.009198,
+    -0.004516, 0.000000, 0.003961, 0.007071, 0.009145, 0.010112, 0.010010, 0.008968,
+    0.007191, 0.004927, 0.002443, 0.000000, -0.002175, -0.003903, -0.005069, -0.005621,
+    -0.005574, -0.004998, -0.004006, -0.002742, -0.001357, 0.000000, 0.001200, 0.002144,
+    0.002770, 0.003053, 0.003006,
+];
+
+/// Convolves a stream of C4FM impulses (as produced by `C4fmImpulses`) with the P25
+/// C4FM shaping filter, yielding the modulated transmit samples.
+pub struct C4fmFilter<T> {
+    /// The impulse source to filter.
+    src: T,
+    /// Circular buffer of the last `SHAPING_TAPS.len()` impulse samples, most recent
+    /// at `pos`.
+    delay: [f32; SHAPING_TAPS.len()],
+    /// Index of the most recently pushed sample in `delay`.
+    pos: usize,
+}
+
+impl<T: Iterator<Item = f32>> C4fmFilter<T> {
+    /// Construct a new `C4fmFilter<T>` from the given impulse source.
+    pub fn new(src: T) -> C4fmFilter<T> {
+        C4fmFilter {
+            src: src,
+            delay: [0.0; SHAPING_TAPS.len()],
+            pos: 0,
+        }
+    }
+}
+
+impl<T: Iterator<Item = f32>> Iterator for C4fmFilter<T> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let impulse = match self.src.next() {
+            Some(s) => s,
+            None => return None,
+        };
+
+        self.pos = (self.pos + 1) % self.delay.len();
+        self.delay[self.pos] = impulse;
+
+        let sample = (0..self.delay.len()).fold(0.0, |acc, i| {
+            let sample = self.delay[(self.pos + self.delay.len() - i) % self.delay.len()];
+            acc + sample * SHAPING_TAPS[i]
+        });
+
+        Some(sample)
+    }
+}
+
 /// Generates the alternating series of dibits used for the C4FM deviation test. The
 /// resulting filtered waveform approximates a 1200Hz sine wave.
 pub struct C4fmDeviationDibits {
@@ -152,4 +220,45 @@ mod test {
         assert!(d.next().unwrap().bits() == 0b11);
         assert!(d.next().unwrap().bits() == 0b11);
     }
+
+    #[test]
+    fn test_filter_taps_sum_to_one() {
+        // Unity DC gain: a constant input should pass through unchanged once the
+        // delay line has filled.
+        let sum: f32 = SHAPING_TAPS.iter().sum();
+        assert!((sum - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_filter_impulse_response() {
+        // A unit impulse's response is exactly the filter's own taps, in order, then
+        // zero once it's flushed through the delay line.
+        let imp = std::iter::once(1.0f32).chain(std::iter::repeat(0.0));
+        let mut filtered = C4fmFilter::new(imp);
+
+        for &tap in SHAPING_TAPS.iter() {
+            assert!((filtered.next().unwrap() - tap).abs() < 1e-6);
+        }
+
+        for _ in 0..5 {
+            assert!((filtered.next().unwrap() - 0.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_filter_is_finite() {
+        const BITS: &'static [u8] = &[0b00011011, 0b01101000];
+
+        let d = bits::Dibits::new(BITS.iter().cloned());
+        let imp = C4fmImpulses::new(d);
+        let mut filtered = C4fmFilter::new(imp);
+
+        let mut count = 0;
+        while let Some(sample) = filtered.next() {
+            assert!(sample.is_finite());
+            count += 1;
+        }
+
+        assert_eq!(count, consts::SYMBOL_PERIOD * 8);
+    }
 }
@@ -1,51 +1,97 @@
 //! Encoding and decoding of the (63, 16, 23) BCH code described by P25.
 //!
 //! These algorithms are derived from *Coding Theory and Cryptography: The Essentials*,
-//! Hankerson, Hoffman, et al, 2000.
+//! Hankerson, Hoffman, et al, 2000. The syndrome computation uses the additive FFT of
+//! Gao and Mateer, *Additive Fast Fourier Transforms for Finite Fields*, 2010.
+//!
+//! The code itself is one instantiation of the generic systematic codec in
+//! `coding::code`. That codec's `encode`/`decode` are binary-alphabet-only for now
+//! (see its module doc), so it doesn't yet cover P25's Reed-Solomon codes used for
+//! link-control and trunking blocks -- those will need a symbol-oriented encode/decode
+//! path added to `coding::code` first.
 
 use std;
 
+use coding::code::{self, Code, FieldElement};
 use coding::galois::{GaloisField, P25Field, P25Codeword, Polynomial, PolynomialCoefs};
-use coding::bmcf;
+
+impl FieldElement for P25Codeword {
+    fn for_power(pow: usize) -> Self { P25Codeword::for_power(pow) }
+    fn power(&self) -> Option<usize> { P25Codeword::power(self) }
+}
+
+/// The P25 NID BCH code: length 63, dimension 16, designed distance 23.
+///
+/// `static` rather than `const`: `Code` caches its FFT index table the first time
+/// `syndromes()` runs, and that cache only does any good if every call shares this one
+/// instance instead of each reinitializing a fresh `const` copy.
+static P25_NID: Code<P25Field, P25Codeword, 24> = Code::new(GEN, 63, 16);
 
 /// Encode the 16 data bits into a 64-bit codeword.
 pub fn encode(word: u16) -> u64 {
-    matrix_mul_systematic!(word, GEN, u64)
+    P25_NID.encode(word)
 }
 
 /// Try to decode the 64-bit word to the nearest codeword, correcting up to 11 errors.
 /// Return `Some((data, err))`, where `data` is the 16 data bits and `err` is the number
 /// of errors, if the codeword could be corrected and `None` if it couldn't.
 pub fn decode(word: u64) -> Option<(u16, usize)> {
-    // The BCH code is only over the first 63 bits, so strip off the P25 parity bit.
-    let word = word >> 1;
-
-    // Compute the syndrome polynomial.
-    let syn = syndromes(word);
-
-    // Get the error location polynomial.
-    let poly = BCHDecoder::new(syn).decode();
-
-    // The degree indicates the number of errors that need to be corrected.
-    let errors = poly.degree().expect("invalid error polynomial");
-
-    // Get the error locations from the polynomial.
-    let locs = bmcf::Errors::new(poly, syn);
-
-    // Correct the codeword and count the number of corrected errors. Stop the iteration
-    // after `errors` iterations since it won't yield any more locations after that
-    // anyway.
-    let (word, count) = locs.take(errors).fold((word, 0), |(w, s), (loc, val)| {
-        assert!(val.power().unwrap() == 0);
-        (w ^ 1 << loc, s + 1)
-    });
-
-    if count == errors {
-        // Strip off the (corrected) parity-check bits.
-        Some(((word >> 47) as u16, errors))
-    } else {
-        None
+    // The BCH code is only over the first 63 bits, so strip off the P25 parity bit
+    // before handing the word to the generic codec.
+    P25_NID.decode(word >> 1)
+}
+
+/// Encode a batch of data words by splitting the work across worker threads spawned
+/// for this call, returning the codewords in the same order as `words`. See
+/// `decode_batch` for how the work is partitioned.
+pub fn encode_batch(words: &[u16], threads: Option<usize>) -> Vec<u64> {
+    batch(words, threads, |&w| encode(w))
+}
+
+/// Decode a batch of codewords by splitting the work across worker threads spawned for
+/// this call, returning the results in the same order as `words`. Each `decode()` call
+/// is independent of every other, so the batch is split into per-thread ranges, each
+/// range decoded locally on its own thread, and the results reassembled in order.
+///
+/// `threads` selects how many worker threads to spread the batch across; `None`
+/// defaults to the number of available CPUs. This spawns and joins a fresh set of
+/// threads for every call rather than reusing a persistent pool, so it only pays off
+/// once each thread's share of the batch comfortably outweighs that spawn/join cost --
+/// for small batches, the scalar `decode()` loop is likely faster.
+pub fn decode_batch(words: &[u64], threads: Option<usize>) -> Vec<Option<(u16, usize)>> {
+    batch(words, threads, |&w| decode(w))
+}
+
+/// Split `items` into at most `threads` contiguous chunks, map `f` over each chunk on
+/// its own freshly spawned thread, and reassemble the results in input order. Not a persistent
+/// thread pool -- each call pays its own thread spawn/join cost -- so `threads == 1`
+/// skips spawning entirely and just maps `f` over `items` in place.
+fn batch<T, R, F>(items: &[T], threads: Option<usize>, f: F) -> Vec<R>
+    where T: Sync, R: Send, F: Fn(&T) -> R + Sync
+{
+    if items.is_empty() {
+        return Vec::new();
     }
+
+    let threads = threads.unwrap_or_else(|| {
+        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    }).max(1);
+
+    if threads == 1 {
+        return items.iter().map(&f).collect();
+    }
+
+    let chunk_size = (items.len() + threads - 1) / threads;
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = items.chunks(chunk_size).map(|chunk| {
+            scope.spawn(|| chunk.iter().map(&f).collect::<Vec<R>>())
+        }).collect();
+
+        handles.into_iter()
+            .flat_map(|handle| handle.join().expect("worker thread panicked"))
+            .collect()
+    })
 }
 
 /// Generator matrix from P25, transformed for more efficient codeword generation.
@@ -100,46 +146,14 @@ const GEN: &'static [u16] = &[
     0b0000000000000011,
 ];
 
-#[derive(Copy, Clone, Default)]
-struct BCHCoefs([P25Codeword; 22 + 2]);
-
-impl std::ops::Deref for BCHCoefs {
-    type Target = [P25Codeword];
-    fn deref(&self) -> &Self::Target { &self.0[..] }
-}
-
-impl std::ops::DerefMut for BCHCoefs {
-    fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0[..] }
-}
-
-impl PolynomialCoefs for BCHCoefs {
-    fn distance() -> usize { 23 }
-}
-
+type BCHCoefs = code::Coefs<P25Codeword, 24>;
 type BCHPolynomial = Polynomial<BCHCoefs>;
-type BCHDecoder = bmcf::BerlMasseyDecoder<BCHCoefs>;
-
-/// Generate the syndrome polynomial for the given received word.
-fn syndromes(word: u64) -> BCHPolynomial {
-    BCHPolynomial::new(std::iter::once(P25Codeword::for_power(0))
-        .chain((1..BCHCoefs::distance()).map(|pow| {
-            (0..P25Field::size()).fold(P25Codeword::default(), |s, b| {
-                if word >> b & 1 == 0 {
-                    s
-                } else {
-                    s + P25Codeword::for_power(b * pow)
-                }
-            })
-        }))
-    )
-}
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use super::{syndromes, BCHDecoder, BCHCoefs, BCHPolynomial};
     use coding::galois::{P25Codeword, PolynomialCoefs};
-    use coding::bmcf::Errors;
+    use coding::bmcf::{BerlMasseyDecoder, Errors};
 
     #[test]
     fn validate_coefs() {
@@ -159,18 +173,18 @@ mod test {
     #[test]
     fn test_syndromes() {
         let w = encode(0b1111111100000000)>>1;
-        let p = syndromes(w);
+        let p = P25_NID.syndromes(w);
 
         assert_eq!(p.len(), 24);
         assert_eq!(p.degree().unwrap(), 0);
-        assert_eq!(syndromes(w ^ 1<<60).degree().unwrap(), 22);
+        assert_eq!(P25_NID.syndromes(w ^ 1<<60).degree().unwrap(), 22);
         assert!(p[0] == P25Codeword::for_power(0));
     }
 
     #[test]
     fn test_decoder() {
         let w = encode(0b1111111100000000)^0b11<<61;
-        let poly = BCHDecoder::new(syndromes(w >> 1)).decode();
+        let poly = BerlMasseyDecoder::<BCHCoefs>::new(P25_NID.syndromes(w >> 1)).decode();
 
         assert!(poly.coef(0).power().unwrap() == 0);
         assert!(poly.coef(1).power().unwrap() == 3);
@@ -180,7 +194,7 @@ mod test {
     #[test]
     fn test_locs() {
         let w = encode(0b0000111100001111)^0b11<<61;
-        let p = syndromes(w >> 1);
+        let p = P25_NID.syndromes(w >> 1);
 
         let coefs = BCHPolynomial::new([
             P25Codeword::for_power(0),
@@ -230,4 +244,32 @@ mod test {
             assert_eq!(decode(encode(i as u16)).unwrap().0, i as u16);
         }
     }
+
+    #[test]
+    fn test_encode_batch() {
+        let words: Vec<u16> = (0..1000).map(|i| i as u16).collect();
+
+        let batched = encode_batch(&words, Some(4));
+        let scalar: Vec<u64> = words.iter().map(|&w| encode(w)).collect();
+
+        assert_eq!(batched, scalar);
+    }
+
+    #[test]
+    fn test_decode_batch() {
+        let codewords: Vec<u64> = (0..1000u32)
+            .map(|i| encode(i as u16) ^ (i as u64 & 0b111))
+            .collect();
+
+        let batched = decode_batch(&codewords, Some(4));
+        let scalar: Vec<Option<(u16, usize)>> = codewords.iter().map(|&w| decode(w)).collect();
+
+        assert_eq!(batched, scalar);
+    }
+
+    #[test]
+    fn test_decode_batch_default_threads() {
+        let codewords: Vec<u64> = (0..50u32).map(|i| encode(i as u16)).collect();
+        assert_eq!(decode_batch(&codewords, None), decode_batch(&codewords, Some(1)));
+    }
 }
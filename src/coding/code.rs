@@ -0,0 +1,280 @@
+//! A generic systematic codec, parameterized over a Galois field, factoring out the
+//! syndrome / Berlekamp-Massey / Chien-search pipeline shared by P25's algebraic
+//! codes.
+//!
+//! Only the P25 NID BCH code (`coding::bch`) instantiates it so far. `Code`'s
+//! `encode`/`decode` are binary-alphabet-only: `encode` packs a `u16` into a `u64`
+//! codeword one bit per symbol, and `decode`'s error correction assumes every located
+//! error's value is the field's multiplicative identity, so it can be corrected by
+//! flipping a single bit -- both true of a binary BCH code, neither true of a
+//! Reed-Solomon code, where codeword symbols are arbitrary field elements and error
+//! values can be any nonzero element. Reusing this pipeline for P25's Reed-Solomon
+//! codes (link-control and trunking blocks) will need a symbol-oriented encode/decode
+//! path operating on `&[W]`/`Vec<W>` instead of bit-packed integers, plus Forney-style
+//! error-value correction; neither exists yet.
+
+use std;
+use std::marker::PhantomData;
+use std::sync::OnceLock;
+
+use coding::galois::{GaloisField, Polynomial, PolynomialCoefs};
+use coding::bmcf;
+
+/// The operations the generic codec needs from a `GaloisField`'s codeword type.
+pub trait FieldElement:
+    Copy + Default + PartialEq +
+    std::ops::Add<Output = Self> + std::ops::Mul<Output = Self>
+{
+    /// The element `α^pow`, where `α` is the field's primitive element.
+    fn for_power(pow: usize) -> Self;
+    /// This element's discrete log, or `None` if it's zero.
+    fn power(&self) -> Option<usize>;
+}
+
+/// Fixed-size coefficient storage for a `Polynomial` over `W`. `N` must be one more
+/// than the code's designed distance.
+#[derive(Copy, Clone)]
+pub struct Coefs<W, const N: usize>([W; N]);
+
+impl<W: Copy + Default, const N: usize> Default for Coefs<W, N> {
+    fn default() -> Self { Coefs([W::default(); N]) }
+}
+
+impl<W, const N: usize> std::ops::Deref for Coefs<W, N> {
+    type Target = [W];
+    fn deref(&self) -> &Self::Target { &self.0[..] }
+}
+
+impl<W, const N: usize> std::ops::DerefMut for Coefs<W, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0[..] }
+}
+
+impl<W: Copy + Default, const N: usize> PolynomialCoefs for Coefs<W, N> {
+    fn distance() -> usize { N - 1 }
+}
+
+/// A systematic code over field `F` (with codeword type `W`): a generator matrix,
+/// transformed as in `coding::bch::GEN`, for a code of length `n` and dimension `k`
+/// and designed distance `N - 1`.
+pub struct Code<F, W, const N: usize> {
+    generator: &'static [u16],
+    n: usize,
+    k: usize,
+    /// Cache of `power_to_fft_index`'s table, built on first use. Shared across every
+    /// call on this `Code`, so instances meant to pay for it once should be `static`
+    /// rather than `const` -- a `const` is inlined at each use site and would rebuild
+    /// the table every time.
+    fft_index: OnceLock<Vec<usize>>,
+    field: PhantomData<F>,
+    word: PhantomData<W>,
+}
+
+impl<F: GaloisField, W: FieldElement, const N: usize> Code<F, W, N> {
+    /// Construct a code from its (already row-reduced) generator matrix, codeword
+    /// length `n`, and data dimension `k`.
+    pub const fn new(generator: &'static [u16], n: usize, k: usize) -> Code<F, W, N> {
+        Code {
+            generator: generator,
+            n: n,
+            k: k,
+            fft_index: OnceLock::new(),
+            field: PhantomData,
+            word: PhantomData,
+        }
+    }
+
+    /// Encode the `k`-bit data word into a systematic codeword of `k + generator.len()`
+    /// bits, with the data in the top `k` bits.
+    pub fn encode(&self, word: u16) -> u64 {
+        matrix_mul_systematic!(word, self.generator, u64)
+    }
+
+    /// Try to decode the `n`-bit received word to the nearest codeword. Return
+    /// `Some((data, err))`, where `data` is the `k` data bits and `err` is the number
+    /// of errors, if the codeword could be corrected and `None` if it couldn't.
+    pub fn decode(&self, word: u64) -> Option<(u16, usize)> {
+        let syn = self.syndromes(word);
+        let poly = bmcf::BerlMasseyDecoder::<Coefs<W, N>>::new(syn).decode();
+        let errors = poly.degree().expect("invalid error polynomial");
+
+        let locs = bmcf::Errors::new(poly, syn);
+        let (word, count) = locs.take(errors).fold((word, 0), |(w, s), (loc, val)| {
+            assert!(val.power().unwrap() == 0);
+            (w ^ 1 << loc, s + 1)
+        });
+
+        if count == errors {
+            Some(((word >> (self.n - self.k)) as u16, errors))
+        } else {
+            None
+        }
+    }
+
+    /// Generate the syndrome polynomial for the given received word, using the
+    /// Gao-Mateer additive FFT to evaluate the received polynomial at every nonzero
+    /// field element at once, then reading off the `N - 2` syndromes the decoder
+    /// needs.
+    pub(crate) fn syndromes(&self, word: u64) -> Polynomial<Coefs<W, N>> {
+        let size = F::size();
+        let dimension = size.trailing_zeros() as usize;
+
+        let mut coefs = vec![W::default(); size];
+        for b in 0..self.n {
+            if word >> b & 1 != 0 {
+                coefs[b] = W::for_power(0);
+            }
+        }
+
+        let basis: Vec<W> = (0..dimension).map(W::for_power).collect();
+        let evals = additive_fft(&coefs, &basis);
+        let index = self.fft_index.get_or_init(|| power_to_fft_index(&basis, size));
+
+        Polynomial::new(std::iter::once(W::for_power(0))
+            .chain((1..N - 1).map(|pow| evals[index[pow]])))
+    }
+}
+
+/// The field element represented by `idx`'s set bits, taken as coordinates against
+/// `basis`: the sum of the basis vectors it selects.
+fn coset_point<W: FieldElement>(basis: &[W], idx: usize) -> W {
+    (0..basis.len()).fold(W::default(), |acc, i| {
+        if idx >> i & 1 == 0 {
+            acc
+        } else {
+            acc + basis[i]
+        }
+    })
+}
+
+/// Evaluate `coefs` (the received polynomial, `coefs.len()` a power of two) at every
+/// point of the subspace spanned by `basis`, using the Gao-Mateer additive FFT. Output
+/// index `idx` holds the evaluation at `coset_point(basis, idx)`.
+///
+/// With `b0 = basis[0]` and `q(x) = x² + b0·x`, `f(x) = r0(q(x)) + x·r1(q(x))`. Since
+/// `q(β + b0) = q(β)`, both `f(β)` and `f(β + b0)` follow from `r0` and `r1` evaluated
+/// at `q(β)`, which recurses over the subspace spanned by `q(basis[1..])`.
+fn additive_fft<W: FieldElement>(coefs: &[W], basis: &[W]) -> Vec<W> {
+    if basis.is_empty() {
+        return vec![coefs[0]];
+    }
+
+    let half = coefs.len() / 2;
+    let b0 = basis[0];
+    let (r0, r1) = taylor_expand(coefs, half, b0);
+
+    let sub_basis: Vec<W> = basis[1..].iter()
+        .map(|&b| b * b + b0 * b)
+        .collect();
+
+    let e0 = additive_fft(&r0, &sub_basis);
+    let e1 = additive_fft(&r1, &sub_basis);
+
+    let mut out = vec![W::default(); coefs.len()];
+
+    for i in 0..half {
+        let beta = coset_point(&basis[1..], i);
+        let r_beta = e0[i] + beta * e1[i];
+
+        out[2 * i] = r_beta;
+        out[2 * i + 1] = r_beta + b0 * e1[i];
+    }
+
+    out
+}
+
+/// Split `f` (length `2 * half`) into `(r0, r1)`, each of length `half`, such that
+/// `f(x) = r0(x²+b0·x) + x·r1(x²+b0·x)`. Each monomial `x^k = p(y) + x·q(y)`, with
+/// `y = x²+b0·x`, gives `x^(k+1) = y·q(y) + x·(p(y) + b0·q(y))`.
+fn taylor_expand<W: FieldElement>(f: &[W], half: usize, b0: W) -> (Vec<W>, Vec<W>) {
+    let mut r0 = vec![W::default(); half];
+    let mut r1 = vec![W::default(); half];
+
+    let mut p = vec![W::default(); half];
+    let mut q = vec![W::default(); half];
+    p[0] = W::for_power(0);
+
+    for &coef in f.iter() {
+        for i in 0..half {
+            r0[i] = r0[i] + coef * p[i];
+            r1[i] = r1[i] + coef * q[i];
+        }
+
+        let mut next_p = vec![W::default(); half];
+        let mut next_q = vec![W::default(); half];
+
+        for i in 0..half - 1 {
+            next_p[i + 1] = q[i];
+        }
+        for i in 0..half {
+            next_q[i] = p[i] + b0 * q[i];
+        }
+
+        p = next_p;
+        q = next_q;
+    }
+
+    (r0, r1)
+}
+
+/// Maps a syndrome power `j` to the index into `additive_fft`'s output at which
+/// `r(α^j)` ends up, for the given `basis`. Depends only on the field and basis, never
+/// on the received word, so `Code::syndromes` computes this once per `Code` and caches
+/// it in `fft_index` rather than rebuilding it on every call.
+fn power_to_fft_index<W: FieldElement>(basis: &[W], size: usize) -> Vec<usize> {
+    let mut table = vec![0; size];
+
+    for idx in 0..size {
+        if let Some(pow) = coset_point(basis, idx).power() {
+            table[pow] = idx;
+        }
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use coding::galois::{P25Codeword, P25Field};
+
+    /// A throwaway instantiation for testing `syndromes()` in isolation: the generator
+    /// matrix is never consulted, since syndrome computation depends only on `n`.
+    const TEST: Code<P25Field, P25Codeword, 24> = Code::new(&[], 63, 16);
+
+    /// The nested-fold evaluation `syndromes` replaced with an additive FFT, kept here
+    /// only as a test oracle.
+    fn syndromes_bruteforce(word: u64, n: usize) -> Polynomial<Coefs<P25Codeword, 24>> {
+        Polynomial::new(std::iter::once(P25Codeword::for_power(0))
+            .chain((1..23).map(|pow| {
+                (0..n).fold(P25Codeword::default(), |s, b| {
+                    if word >> b & 1 == 0 {
+                        s
+                    } else {
+                        s + P25Codeword::for_power(b * pow)
+                    }
+                })
+            }))
+        )
+    }
+
+    #[test]
+    fn test_syndromes_matches_bruteforce() {
+        let words = [
+            0,
+            1,
+            0b11 << 60,
+            0b1010101010101010,
+            0b0000111100001111 ^ 0b11 << 61,
+            (1 << 63) - 1,
+        ];
+
+        for &w in words.iter() {
+            let fast = TEST.syndromes(w);
+            let slow = syndromes_bruteforce(w, TEST.n);
+
+            for i in 0..fast.len() {
+                assert!(fast[i] == slow[i]);
+            }
+        }
+    }
+}